@@ -4,15 +4,17 @@ use ic_cdk::{caller, trap};
 use ic_cdk_macros::*;
 use ic_stable_structures::memory_manager::{MemoryId, MemoryManager, VirtualMemory};
 use ic_stable_structures::{DefaultMemoryImpl, StableBTreeMap};
+use sha2::{Digest, Sha256};
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
 
 // Define memory and stable structures
 type Memory = VirtualMemory<DefaultMemoryImpl>;
 
 // Define the core structures for our Access Control System
 
-#[derive(CandidType, Clone, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Role {
     Admin,
     Manager,
@@ -20,6 +22,20 @@ pub enum Role {
     Guest,
 }
 
+// Implement Storable for Role so it can be used as a StableBTreeMap key (ROLE_GRAPH).
+impl ic_stable_structures::Storable for Role {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        let bytes = candid::encode_one(self).unwrap();
+        std::borrow::Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        candid::decode_one(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
 #[derive(CandidType, Clone, Deserialize, Debug, PartialEq, Eq)]
 pub enum RequestStatus {
     Pending,
@@ -27,14 +43,34 @@ pub enum RequestStatus {
     Denied,
 }
 
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Read,
+    Write,
+    Delete,
+    Grant,
+    Custom(String),
+}
+
 #[derive(CandidType, Clone, Deserialize, Debug)]
 pub struct AccessRequest {
     id: String,
     requester: Principal,
     resource: String,
+    action: Action,
     requested_at: u64,
     status: RequestStatus,
     processed: bool,
+    approvals: Vec<Principal>,
+    denials: Vec<Principal>,
+}
+
+// Snapshot of the votes cast so far on a request, returned by `get_request_votes`.
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct RequestVotes {
+    approvals: Vec<Principal>,
+    denials: Vec<Principal>,
+    threshold: u32,
 }
 
 // Implement Storable for AccessRequest
@@ -47,54 +83,209 @@ impl ic_stable_structures::Storable for AccessRequest {
     fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
         candid::decode_one(&bytes).unwrap()
     }
-    
+
     // Define maximum byte size (required for BOUND)
     const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
 }
 
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub enum AuditOperation {
+    UserAdded,
+    UserRemoved,
+    PermissionChanged,
+    RequestApproved,
+    RequestDenied,
+    GrantExpired,
+}
+
+// A single append-only audit record. `prev_hash`/`entry_hash` chain each entry to the one
+// before it, so an upgrade (or anything else) that rewrites history without recomputing every
+// subsequent hash is detectable by `verify_audit_chain`.
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct AuditEntry {
+    seq: u64,
+    caller: Principal,
+    operation: AuditOperation,
+    affected_ids: Vec<String>,
+    timestamp: u64,
+    prev_hash: Vec<u8>,
+    entry_hash: Vec<u8>,
+}
+
+// The fields that get hashed into `entry_hash`, kept separate from `AuditEntry` so the hash
+// preimage never includes the hash fields themselves.
+#[derive(CandidType, Clone)]
+struct AuditPreimage {
+    seq: u64,
+    caller: Principal,
+    operation: AuditOperation,
+    affected_ids: Vec<String>,
+    timestamp: u64,
+}
+
+fn compute_entry_hash(
+    prev_hash: &[u8],
+    seq: u64,
+    caller: Principal,
+    operation: &AuditOperation,
+    affected_ids: &[String],
+    timestamp: u64,
+) -> Vec<u8> {
+    let preimage = AuditPreimage {
+        seq,
+        caller,
+        operation: operation.clone(),
+        affected_ids: affected_ids.to_vec(),
+        timestamp,
+    };
+    let encoded = candid::encode_one(&preimage).unwrap();
+
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash);
+    hasher.update(&encoded);
+    hasher.finalize().to_vec()
+}
+
+// Implement Storable for AuditEntry
+impl ic_stable_structures::Storable for AuditEntry {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        let bytes = candid::encode_one(self).unwrap();
+        std::borrow::Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        candid::decode_one(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// A time-bounded privilege: `action` on the resource encoded in the map key, good until
+// `expires_at` (nanoseconds since epoch, per `ic_cdk::api::time()`).
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct GrantExpiry {
+    action: Action,
+    expires_at: u64,
+}
+
+// Implement Storable for GrantExpiry
+impl ic_stable_structures::Storable for GrantExpiry {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        let bytes = candid::encode_one(self).unwrap();
+        std::borrow::Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        candid::decode_one(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// The set of roles a given role directly inherits from, as stored in ROLE_GRAPH. A newtype is
+// needed since neither `Vec<Role>` nor `Storable` is local to this crate.
+#[derive(CandidType, Clone, Deserialize, Debug, Default)]
+pub struct RoleSet(Vec<Role>);
+
+impl RoleSet {
+    fn into_set(self) -> HashSet<Role> {
+        self.0.into_iter().collect()
+    }
+}
+
+impl From<HashSet<Role>> for RoleSet {
+    fn from(roles: HashSet<Role>) -> Self {
+        RoleSet(roles.into_iter().collect())
+    }
+}
+
+// Implement Storable for RoleSet
+impl ic_stable_structures::Storable for RoleSet {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        let bytes = candid::encode_one(self).unwrap();
+        std::borrow::Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        candid::decode_one(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// A single-use, short-lived capability letting `issuer` delegate one pre-authorized action on
+// `resource` to `grantee`, without granting the delegate any standing role or privilege.
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct Capability {
+    id: String,
+    issuer: Principal,
+    grantee: Principal,
+    resource: String,
+    action: Action,
+    expires_at: u64,
+    redeemed: bool,
+}
+
+// Implement Storable for Capability
+impl ic_stable_structures::Storable for Capability {
+    fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
+        let bytes = candid::encode_one(self).unwrap();
+        std::borrow::Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        candid::decode_one(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound = ic_stable_structures::storable::Bound::Unbounded;
+}
+
 // Define processing guard to prevent double processing
 pub struct RequestProcessingGuard {
-    request_id: String,
+    processing_key: String,
 }
 
 impl RequestProcessingGuard {
-    pub fn new(request_id: String) -> Result<Self, String> {
-        // Verify the request exists and is not already processed
-        let request_exists = ACCESS_REQUESTS.with(|requests| {
-            requests.borrow().get(&request_id).is_some()
+    // Guards a single voter's vote against concurrent mutation of the same request. It does NOT
+    // imply the request is finalized by the call it guards - with M-of-N quorum, a request may
+    // take several successful votes (each through its own guard) before reaching a final status.
+    // The in-flight set is keyed by (request_id, voter) rather than request_id alone, so distinct
+    // eligible approvers can vote on the same request concurrently; only a given voter racing
+    // themselves (e.g. a retried call) is blocked.
+    pub fn new(request_id: String, voter: Principal) -> Result<Self, String> {
+        // Verify the request exists and has not already reached a final status
+        let request = ACCESS_REQUESTS.with(|requests| {
+            requests.borrow().get(&request_id)
         });
-        
-        if !request_exists {
-            return Err("Request does not exist".to_string());
+
+        let request = match request {
+            Some(request) => request,
+            None => return Err("Request does not exist".to_string()),
+        };
+
+        if request.status != RequestStatus::Pending {
+            return Err("Request has already been processed".to_string());
         }
-        
+
         let is_pending = PENDING_REQUESTS.with(|pending| {
             pending.borrow().contains(&request_id)
         });
-        
+
         if !is_pending {
             return Err("Request is not pending".to_string());
         }
-        
-        let is_processed = ACCESS_REQUESTS.with(|requests| {
-            requests.borrow().get(&request_id)
-                .map(|req| req.processed)
-                .unwrap_or(false)
-        });
-        
-        if is_processed {
-            return Err("Request has already been processed".to_string());
-        }
-        
+
+        let processing_key = format!("{}::{}", request_id, voter.to_text());
+
         // Add to processing requests set
         PROCESSING_REQUESTS.with(|processing| {
-            if !processing.borrow_mut().insert(request_id.clone()) {
-                return Err("Request is already being processed".to_string());
+            if !processing.borrow_mut().insert(processing_key.clone()) {
+                return Err("Caller is already voting on this request".to_string());
             }
             Ok(())
         })?;
-        
-        Ok(Self { request_id })
+
+        Ok(Self { processing_key })
     }
 }
 
@@ -102,28 +293,236 @@ impl Drop for RequestProcessingGuard {
     fn drop(&mut self) {
         // Remove from processing set when guard is dropped
         PROCESSING_REQUESTS.with(|processing| {
-            processing.borrow_mut().remove(&self.request_id);
+            processing.borrow_mut().remove(&self.processing_key);
         });
     }
 }
 
+// Default role hierarchy: Admin inherits Manager, which inherits User, which inherits Guest.
+// Admin therefore transitively inherits every other role instead of being special-cased.
+fn default_role_graph() -> Vec<(Role, HashSet<Role>)> {
+    vec![
+        (Role::Admin, HashSet::from([Role::Manager])),
+        (Role::Manager, HashSet::from([Role::User])),
+        (Role::User, HashSet::from([Role::Guest])),
+        (Role::Guest, HashSet::new()),
+    ]
+}
+
+// Seeds ROLE_GRAPH with the default role hierarchy. Only called from `init` - a canister that
+// has been upgraded keeps whatever edges (including admin-added ones) are already stable.
+fn seed_default_role_graph() {
+    ROLE_GRAPH.with(|graph| {
+        let mut graph = graph.borrow_mut();
+        for (role, parents) in default_role_graph() {
+            graph.insert(role, RoleSet::from(parents));
+        }
+    });
+}
+
 // Thread-local storage for our state
 thread_local! {
     // In-memory state
     static USERS: RefCell<HashMap<Principal, Role>> = RefCell::new(HashMap::new());
-    static RESOURCE_PERMISSIONS: RefCell<HashMap<String, HashSet<Role>>> = RefCell::new(HashMap::new());
+    static RESOURCE_PERMISSIONS: RefCell<HashMap<String, HashSet<(Role, Action)>>> = RefCell::new(HashMap::new());
     static PENDING_REQUESTS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
     static PROCESSING_REQUESTS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
-    
+
+    // Next sequence number to assign in AUDIT_LOG.
+    static AUDIT_SEQ: RefCell<u64> = RefCell::new(0);
+
     // Stable storage
-    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> = 
+    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
         RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
-    
+
     static ACCESS_REQUESTS: RefCell<StableBTreeMap<String, AccessRequest, Memory>> = RefCell::new(
         StableBTreeMap::init(
             MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(0)))
         )
     );
+
+    static AUDIT_LOG: RefCell<StableBTreeMap<u64, AuditEntry, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(1)))
+        )
+    );
+
+    // Per-resource approval thresholds (number of approving votes required). Resources with no
+    // entry default to a threshold of 1, matching the original single-approver behavior.
+    static APPROVAL_THRESHOLDS: RefCell<StableBTreeMap<String, u32, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(2)))
+        )
+    );
+
+    // Time-bounded grants, keyed by `grant_key(user, resource, action)`.
+    static GRANT_EXPIRIES: RefCell<StableBTreeMap<String, GrantExpiry, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(3)))
+        )
+    );
+
+    // Delegated, single-use capability tokens, keyed by capability id.
+    static CAPABILITIES: RefCell<StableBTreeMap<String, Capability, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(4)))
+        )
+    );
+
+    // Role inheritance graph: role -> directly inherited roles. Stable so admin-added edges
+    // (and the default hierarchy) survive an upgrade instead of resetting every time.
+    static ROLE_GRAPH: RefCell<StableBTreeMap<Role, RoleSet, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(5)))
+        )
+    );
+}
+
+// Distinct actions on the same resource must not clobber each other's grant, so the action is
+// folded into the key alongside the user and resource.
+fn grant_key(user: &Principal, resource: &str, action: &Action) -> String {
+    format!("{}::{}::{:?}", user.to_text(), resource, action)
+}
+
+// AUDIT_SEQ is a plain in-memory counter, but AUDIT_LOG is stable and survives upgrades. Reseed
+// the counter from the persisted log so a routine upgrade doesn't restart at 0 and overwrite
+// (and thereby corrupt) the existing hash chain.
+fn reseed_audit_seq() {
+    let next_seq = AUDIT_LOG.with(|log| log.borrow().iter().last().map_or(0, |(seq, _)| seq + 1));
+    AUDIT_SEQ.with(|seq| *seq.borrow_mut() = next_seq);
+}
+
+// Removes any grants that have passed their expiry and records an audit entry for each.
+fn sweep_expired_grants() {
+    let now = ic_cdk::api::time();
+
+    let expired_keys: Vec<String> = GRANT_EXPIRIES.with(|grants| {
+        grants
+            .borrow()
+            .iter()
+            .filter(|(_, grant)| grant.expires_at <= now)
+            .map(|(key, _)| key)
+            .collect()
+    });
+
+    for key in expired_keys {
+        GRANT_EXPIRIES.with(|grants| {
+            grants.borrow_mut().remove(&key);
+        });
+        append_audit_entry(AuditOperation::GrantExpired, vec![key]);
+    }
+}
+
+// Starts the periodic sweep that expires lapsed grants. Called from both `init` and
+// `post_upgrade` since timers do not survive an upgrade.
+fn start_grant_sweeper() {
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(60), sweep_expired_grants);
+}
+
+const DEFAULT_APPROVAL_THRESHOLD: u32 = 1;
+
+fn get_approval_threshold(resource: &str) -> u32 {
+    APPROVAL_THRESHOLDS.with(|thresholds| {
+        thresholds
+            .borrow()
+            .get(&resource.to_string())
+            .unwrap_or(DEFAULT_APPROVAL_THRESHOLD)
+    })
+}
+
+#[update(guard = "is_admin")]
+fn set_approval_threshold(resource: String, n: u32) {
+    APPROVAL_THRESHOLDS.with(|thresholds| {
+        thresholds.borrow_mut().insert(resource.clone(), n);
+    });
+
+    ic_cdk::println!("Approval threshold for resource {} set to {}", resource, n);
+    append_audit_entry(AuditOperation::PermissionChanged, vec![resource]);
+}
+
+// ===== Audit Log =====
+
+// Appends a new entry to the audit chain, hashing it against the previous entry's hash.
+fn append_audit_entry(operation: AuditOperation, affected_ids: Vec<String>) {
+    let caller = caller();
+    let timestamp = ic_cdk::api::time();
+
+    let seq = AUDIT_SEQ.with(|s| {
+        let mut s = s.borrow_mut();
+        let seq = *s;
+        *s += 1;
+        seq
+    });
+
+    let prev_hash = if seq == 0 {
+        Vec::new()
+    } else {
+        AUDIT_LOG.with(|log| {
+            log.borrow()
+                .get(&(seq - 1))
+                .map(|entry| entry.entry_hash)
+                .unwrap_or_default()
+        })
+    };
+
+    let entry_hash = compute_entry_hash(&prev_hash, seq, caller, &operation, &affected_ids, timestamp);
+
+    let entry = AuditEntry {
+        seq,
+        caller,
+        operation,
+        affected_ids,
+        timestamp,
+        prev_hash,
+        entry_hash,
+    };
+
+    AUDIT_LOG.with(|log| {
+        log.borrow_mut().insert(seq, entry);
+    });
+}
+
+#[query]
+fn verify_audit_chain() -> Result<(), u64> {
+    AUDIT_LOG.with(|log| {
+        let log = log.borrow();
+        let mut expected_prev_hash: Vec<u8> = Vec::new();
+
+        for (seq, entry) in log.iter() {
+            if entry.prev_hash != expected_prev_hash {
+                return Err(seq);
+            }
+
+            let recomputed = compute_entry_hash(
+                &entry.prev_hash,
+                entry.seq,
+                entry.caller,
+                &entry.operation,
+                &entry.affected_ids,
+                entry.timestamp,
+            );
+
+            if recomputed != entry.entry_hash {
+                return Err(seq);
+            }
+
+            expected_prev_hash = entry.entry_hash.clone();
+        }
+
+        Ok(())
+    })
+}
+
+#[query]
+fn get_audit_entries(start: u64, limit: u64) -> Vec<AuditEntry> {
+    AUDIT_LOG.with(|log| {
+        log.borrow()
+            .iter()
+            .filter(|(seq, _)| *seq >= start)
+            .take(limit as usize)
+            .map(|(_, entry)| entry)
+            .collect()
+    })
 }
 
 // Initialize the canister
@@ -135,6 +534,9 @@ fn init() {
     });
     
     ic_cdk::println!("Access Control System initialized with admin: {}", caller.to_string());
+    seed_default_role_graph();
+    reseed_audit_seq();
+    start_grant_sweeper();
 }
 
 // Add a post_upgrade function to ensure the upgrader becomes admin
@@ -147,6 +549,8 @@ fn post_upgrade() {
     });
     
     ic_cdk::println!("Access Control System upgraded by admin: {}", caller.to_string());
+    reseed_audit_seq();
+    start_grant_sweeper();
 }
 
 #[update]
@@ -179,6 +583,7 @@ fn add_user(user: Principal, role: Role) {
     
     let role_str = format!("{:?}", role_clone);
     ic_cdk::println!("User {} added with role {}", user.to_string(), role_str);
+    append_audit_entry(AuditOperation::UserAdded, vec![user.to_text()]);
 }
 
 #[update(guard = "is_admin")]
@@ -186,8 +591,9 @@ fn remove_user(user: Principal) {
     USERS.with(|users| {
         users.borrow_mut().remove(&user);
     });
-    
+
     ic_cdk::println!("User {} removed", user.to_string());
+    append_audit_entry(AuditOperation::UserRemoved, vec![user.to_text()]);
 }
 
 #[query]
@@ -197,66 +603,216 @@ fn get_user_role(user: Principal) -> Option<Role> {
     })
 }
 
+// ===== Role Hierarchy Management =====
+
+// Maximum number of roles we will ever traverse in a single resolution. The role set is fixed
+// and small, so this is a generous safety net rather than a real limit in practice - it exists
+// purely so a malformed graph (e.g. every role pointing at every other role) can't walk an
+// unbounded number of edges and blow the instruction limit.
+const MAX_ROLE_TRAVERSAL: usize = 64;
+
+// Resolves the full set of roles reachable from `role` by following inheritance edges.
+// Cycles (including self-inheritance) are broken by the `visited` set.
+fn resolve_effective_roles(role: &Role) -> HashSet<Role> {
+    let mut visited: HashSet<Role> = HashSet::new();
+    let mut queue: VecDeque<Role> = VecDeque::from([role.clone()]);
+    visited.insert(role.clone());
+
+    while let Some(current) = queue.pop_front() {
+        if visited.len() >= MAX_ROLE_TRAVERSAL {
+            break;
+        }
+
+        ROLE_GRAPH.with(|graph| {
+            if let Some(parents) = graph.borrow().get(&current) {
+                for parent in parents.0 {
+                    if visited.insert(parent.clone()) {
+                        queue.push_back(parent);
+                    }
+                }
+            }
+        });
+    }
+
+    visited
+}
+
+#[update(guard = "is_admin")]
+fn add_role_inheritance(role: Role, inherits_from: Role) {
+    ROLE_GRAPH.with(|graph| {
+        let mut graph = graph.borrow_mut();
+        let mut parents = graph.get(&role).map_or(HashSet::new(), RoleSet::into_set);
+        parents.insert(inherits_from.clone());
+        graph.insert(role.clone(), RoleSet::from(parents));
+    });
+
+    ic_cdk::println!("Role {:?} now inherits from {:?}", role, inherits_from);
+}
+
+#[update(guard = "is_admin")]
+fn remove_role_inheritance(role: Role, inherits_from: Role) {
+    ROLE_GRAPH.with(|graph| {
+        let mut graph = graph.borrow_mut();
+        if let Some(parents) = graph.get(&role) {
+            let mut parents = parents.into_set();
+            parents.remove(&inherits_from);
+            graph.insert(role.clone(), RoleSet::from(parents));
+        }
+    });
+
+    ic_cdk::println!("Role {:?} no longer inherits from {:?}", role, inherits_from);
+}
+
+#[query]
+fn get_effective_roles(user: Principal) -> Vec<Role> {
+    let user_role = match get_user_role(user) {
+        Some(role) => role,
+        None => return Vec::new(),
+    };
+
+    resolve_effective_roles(&user_role).into_iter().collect()
+}
+
 // ===== Resource Permission Management =====
 
-#[update(guard = "is_admin_or_manager")]
-fn add_resource_permission(resource: String, allowed_role: Role) {
+// Grants `action` on `resource` to `role`, without re-checking any guard. Used by the
+// admin/manager-facing `add_resource_permission`.
+fn grant_privilege(resource: &str, role: Role, action: Action) {
     RESOURCE_PERMISSIONS.with(|permissions| {
         permissions
             .borrow_mut()
-            .entry(resource.clone())
+            .entry(resource.to_string())
             .or_insert_with(HashSet::new)
-            .insert(allowed_role.clone());
+            .insert((role, action));
     });
-    
-    ic_cdk::println!("Permission for role {:?} added to resource {}", allowed_role, resource);
 }
 
 #[update(guard = "is_admin_or_manager")]
-fn remove_resource_permission(resource: String, role: Role) {
+fn add_resource_permission(resource: String, allowed_role: Role, action: Action) {
+    grant_privilege(&resource, allowed_role.clone(), action.clone());
+
+    ic_cdk::println!(
+        "Permission for role {:?} to {:?} added to resource {}",
+        allowed_role, action, resource
+    );
+    append_audit_entry(AuditOperation::PermissionChanged, vec![resource]);
+}
+
+#[update(guard = "is_admin_or_manager")]
+fn remove_resource_permission(resource: String, role: Role, action: Action) {
     RESOURCE_PERMISSIONS.with(|permissions| {
-        if let Some(roles) = permissions.borrow_mut().get_mut(&resource) {
-            roles.remove(&role);
+        if let Some(privileges) = permissions.borrow_mut().get_mut(&resource) {
+            privileges.remove(&(role.clone(), action.clone()));
         }
     });
-    
-    ic_cdk::println!("Permission for role {:?} removed from resource {}", role, resource);
+
+    ic_cdk::println!(
+        "Permission for role {:?} to {:?} removed from resource {}",
+        role, action, resource
+    );
+    append_audit_entry(AuditOperation::PermissionChanged, vec![resource]);
+}
+
+// Records a grant of `action` on `resource` to `user`, valid until `expires_at`. Shared by
+// `grant_temporary_access`, request approval in `process_request` and capability redemption.
+fn grant_until(user: Principal, resource: &str, action: Action, expires_at: u64) -> String {
+    let key = grant_key(&user, resource, &action);
+
+    GRANT_EXPIRIES.with(|grants| {
+        grants.borrow_mut().insert(key.clone(), GrantExpiry { action, expires_at });
+    });
+
+    key
+}
+
+#[update(guard = "is_admin_or_manager")]
+fn grant_temporary_access(user: Principal, resource: String, action: Action, duration_ns: u64) {
+    let issuer = caller();
+    if !can_perform(issuer, resource.clone(), action.clone()) {
+        trap("Caller does not hold the privilege being granted");
+    }
+
+    let expires_at = ic_cdk::api::time().saturating_add(duration_ns);
+    let key = grant_until(user, &resource, action.clone(), expires_at);
+
+    ic_cdk::println!(
+        "Temporary {:?} access to {} granted to {} until {}",
+        action, resource, user.to_text(), expires_at
+    );
+    append_audit_entry(AuditOperation::PermissionChanged, vec![key]);
+}
+
+#[query]
+fn get_active_grants(user: Principal) -> Vec<(String, u64)> {
+    let now = ic_cdk::api::time();
+    let prefix = format!("{}::", user.to_text());
+
+    GRANT_EXPIRIES.with(|grants| {
+        grants
+            .borrow()
+            .iter()
+            .filter(|(_, grant)| grant.expires_at > now)
+            .filter_map(|(key, grant)| {
+                // The key is `user::resource::action_debug`; strip the known prefix and the
+                // known action suffix (taken from the stored grant, not reparsed) to recover
+                // `resource` even if it happens to contain "::" itself.
+                let suffix = format!("::{:?}", grant.action);
+                let resource = key.strip_prefix(&prefix)?.strip_suffix(&suffix)?.to_string();
+                Some((resource, grant.expires_at - now))
+            })
+            .collect()
+    })
 }
 
 #[query]
-fn can_access_resource(user: Principal, resource: String) -> bool {
+fn can_perform(user: Principal, resource: String, action: Action) -> bool {
     let user_role = match get_user_role(user) {
         Some(role) => role,
         None => return false,
     };
-    
-    // Admins can access everything
-    if user_role == Role::Admin {
+
+    let effective_roles = resolve_effective_roles(&user_role);
+
+    let role_allowed = RESOURCE_PERMISSIONS.with(|permissions| {
+        permissions.borrow().get(&resource).map_or(false, |privileges| {
+            effective_roles
+                .iter()
+                .any(|role| privileges.contains(&(role.clone(), action.clone())))
+        })
+    });
+
+    if role_allowed {
         return true;
     }
-    
-    RESOURCE_PERMISSIONS.with(|permissions| {
-        permissions
+
+    // Fall back to a matching, unexpired temporary grant for this specific user.
+    let key = grant_key(&user, &resource, &action);
+    let now = ic_cdk::api::time();
+    GRANT_EXPIRIES.with(|grants| {
+        grants
             .borrow()
-            .get(&resource)
-            .map_or(false, |roles| roles.contains(&user_role))
+            .get(&key)
+            .map_or(false, |grant| grant.expires_at > now)
     })
 }
 
 // ===== Access Request Processing =====
 
 #[update]
-fn request_access(resource: String) -> String {
+fn request_access(resource: String, action: Action) -> String {
     let requester = caller();
     let request_id = format!("req-{}-{}", requester.to_text(), ic_cdk::api::time());
-    
+
     let request = AccessRequest {
         id: request_id.clone(),
         requester,
         resource,
+        action,
         requested_at: ic_cdk::api::time(),
         status: RequestStatus::Pending,
         processed: false,
+        approvals: Vec::new(),
+        denials: Vec::new(),
     };
     
     // Store the request
@@ -273,51 +829,111 @@ fn request_access(resource: String) -> String {
     request_id
 }
 
+// Casts `voter`'s vote on `request`, mutating its approvals/denials and deciding whether the
+// request reaches a final status. A single denial denies the request outright (preserving the
+// original single-approver behavior when threshold is 1); approval only finalizes once the
+// resource's approval threshold is met.
+fn cast_vote(mut request: AccessRequest, voter: Principal, approve: bool) -> AccessRequest {
+    if approve {
+        request.approvals.push(voter);
+    } else {
+        request.denials.push(voter);
+    }
+
+    if !approve {
+        request.status = RequestStatus::Denied;
+        request.processed = true;
+    } else {
+        let threshold = get_approval_threshold(&request.resource);
+        if request.approvals.len() as u32 >= threshold {
+            request.status = RequestStatus::Approved;
+            request.processed = true;
+        }
+    }
+
+    request
+}
+
 #[update(guard = "is_admin_or_manager")]
 async fn process_request(request_id: String, approve: bool) {
-    // Create a processing guard that will ensure the request is processed at most once
-    // and protect against parallel processing
-    let guard = match RequestProcessingGuard::new(request_id.clone()) {
+    let voter = caller();
+
+    // Create a processing guard that protects this voter's vote against concurrent mutation of
+    // the same request. It does not, by itself, finalize the request - see `cast_vote`. Keying
+    // by (request_id, voter) lets distinct eligible approvers vote concurrently; it only blocks
+    // the same voter from racing themselves.
+    let guard = match RequestProcessingGuard::new(request_id.clone(), voter) {
         Ok(guard) => guard,
         Err(e) => trap(&e),
     };
-    
-    // Create a scope guard to mark the request as processed if this function completes
-    // This will execute even if the async code fails
+
+    let already_voted = ACCESS_REQUESTS.with(|requests| {
+        requests
+            .borrow()
+            .get(&request_id)
+            .map(|req| req.approvals.contains(&voter) || req.denials.contains(&voter))
+            .unwrap_or(false)
+    });
+
+    if already_voted {
+        trap("Caller has already voted on this request");
+    }
+
+    // Create a scope guard to record the vote and, if it finalizes the request, update its
+    // status. This will execute even if the async code fails.
     let request_id_clone = request_id.clone();
     let _complete_guard = scopeguard::guard((), move |_| {
-        // Mark the request as processed and update status
-        let status = if approve {
-            RequestStatus::Approved
-        } else {
-            RequestStatus::Denied
-        };
-        
-        // Get and update the request
-        let mut updated_request = ACCESS_REQUESTS.with(|requests| {
+        let request = ACCESS_REQUESTS.with(|requests| {
             requests.borrow().get(&request_id_clone).unwrap().clone()
         });
-        
-        updated_request.status = status.clone();
-        updated_request.processed = true;
-        
+
+        // Another approver's vote may have finalized the request while this one was awaiting;
+        // a final status is never overwritten.
+        if request.status != RequestStatus::Pending {
+            return;
+        }
+
+        let updated_request = cast_vote(request, voter, approve);
+        let final_status = updated_request.status.clone();
+
+        // On approval finalization, grant exactly the privilege that was requested - scoped to
+        // the requesting principal, not their whole role, so approving one request doesn't hand
+        // the same access to every other user who happens to share that role.
+        if final_status == RequestStatus::Approved {
+            grant_until(updated_request.requester, &updated_request.resource, updated_request.action.clone(), u64::MAX);
+        }
+
         // Update in storage
         ACCESS_REQUESTS.with(|requests| {
             requests.borrow_mut().insert(request_id_clone.clone(), updated_request);
         });
-        
-        // Remove from pending
-        PENDING_REQUESTS.with(|pending| {
-            pending.borrow_mut().remove(&request_id_clone);
-        });
-        
-        ic_cdk::println!("Request {} processed with status: {:?}", request_id_clone, status);
+
+        // Remove from pending once finalized
+        if final_status != RequestStatus::Pending {
+            PENDING_REQUESTS.with(|pending| {
+                pending.borrow_mut().remove(&request_id_clone);
+            });
+        }
+
+        ic_cdk::println!(
+            "Vote recorded for request {} (approve={}), status now: {:?}",
+            request_id_clone, approve, final_status
+        );
+
+        if final_status != RequestStatus::Pending {
+            let operation = if final_status == RequestStatus::Approved {
+                AuditOperation::RequestApproved
+            } else {
+                AuditOperation::RequestDenied
+            };
+            append_audit_entry(operation, vec![request_id_clone.clone()]);
+        }
     });
-    
+
     // Simulate external call or processing
     // This is the async part where we might yield control
     let result = simulate_external_processing(request_id.clone(), approve).await;
-    
+
     // Handle potential errors from async processing
     if let Err(e) = result {
         ic_cdk::println!("Error in async processing for request {}: {:?}", request_id, e);
@@ -355,6 +971,89 @@ fn get_all_pending_requests() -> Vec<String> {
     })
 }
 
+#[query]
+fn get_request_votes(request_id: String) -> Option<RequestVotes> {
+    ACCESS_REQUESTS.with(|requests| {
+        requests.borrow().get(&request_id).map(|req| RequestVotes {
+            approvals: req.approvals.clone(),
+            denials: req.denials.clone(),
+            threshold: get_approval_threshold(&req.resource),
+        })
+    })
+}
+
+// ===== Capability Delegation =====
+
+#[update(guard = "is_admin_or_manager")]
+fn mint_capability(grantee: Principal, resource: String, action: Action, expires_at: u64) -> String {
+    let issuer = caller();
+    if !can_perform(issuer, resource.clone(), action.clone()) {
+        trap("Caller does not hold the privilege being delegated");
+    }
+
+    let id = format!("cap-{}-{}", grantee.to_text(), ic_cdk::api::time());
+
+    let capability = Capability {
+        id: id.clone(),
+        issuer,
+        grantee,
+        resource,
+        action,
+        expires_at,
+        redeemed: false,
+    };
+
+    CAPABILITIES.with(|capabilities| {
+        capabilities.borrow_mut().insert(id.clone(), capability);
+    });
+
+    ic_cdk::println!("Capability {} minted by {} for {}", id, issuer.to_text(), grantee.to_text());
+    append_audit_entry(AuditOperation::PermissionChanged, vec![id.clone()]);
+    id
+}
+
+#[update]
+fn redeem_capability(id: String) {
+    let caller = caller();
+
+    let capability = match CAPABILITIES.with(|capabilities| capabilities.borrow().get(&id)) {
+        Some(capability) => capability,
+        None => trap("Capability does not exist"),
+    };
+
+    if capability.grantee != caller {
+        trap("Only the designated grantee may redeem this capability");
+    }
+
+    // Redemption is idempotent: redeeming an already-redeemed capability is a no-op rather than
+    // an error, so a retried call can't grant the delegated privilege twice.
+    if capability.redeemed {
+        return;
+    }
+
+    if capability.expires_at <= ic_cdk::api::time() {
+        trap("Capability has expired");
+    }
+
+    grant_until(capability.grantee, &capability.resource, capability.action.clone(), capability.expires_at);
+
+    let mut updated_capability = capability.clone();
+    updated_capability.redeemed = true;
+    CAPABILITIES.with(|capabilities| {
+        capabilities.borrow_mut().insert(id.clone(), updated_capability);
+    });
+
+    ic_cdk::println!("Capability {} redeemed by {}", id, caller.to_text());
+    append_audit_entry(
+        AuditOperation::PermissionChanged,
+        vec![
+            id,
+            format!("issuer:{}", capability.issuer.to_text()),
+            format!("grantee:{}", capability.grantee.to_text()),
+        ],
+    );
+}
+
 // ===== Guard Functions =====
 
 fn is_admin() -> Result<(), String> {